@@ -19,7 +19,8 @@ fn main() {
     assert_eq!(APP_NAME, app.name());
     app.set_style("Material");
     let view = QuickView::new(&app);
-    view.set_source(&Url::from_file_path(Path::new(QML_FILE)).unwrap());
+    view.set_source(&Url::from_file_path(Path::new(QML_FILE)).unwrap())
+        .unwrap();
     view.show();
     let code = app.exec();
     println!("App exited with code {}.", code);
@@ -128,6 +128,40 @@ impl AppRef {
     pub fn set_style(&self, s: &str) {
         unsafe {qlue::qlueAppSetStyle(to_string_view(s));}
     }
+
+    /// Asks the event loop entered by `App::exec` to terminate with exit
+    /// code 0.
+    ///
+    /// This can be called from any thread holding an `AppRef`, which is why
+    /// `AppRef` is `Send` and `Sync`.
+    pub fn quit(&self) {
+        unsafe {qlue::qlueAppQuit();}
+    }
+
+    /// Asks the event loop entered by `App::exec` to terminate with exit
+    /// code `code`.
+    ///
+    /// This can be called from any thread holding an `AppRef`, which is why
+    /// `AppRef` is `Send` and `Sync`.
+    pub fn exit(&self, code: i32) {
+        unsafe {qlue::qlueAppExit(code as c_int);}
+    }
+
+    /// Returns whether the event loop terminates automatically once the
+    /// last visible window is closed.
+    pub fn quit_on_last_window_closed(&self) -> bool {
+        unsafe {qlue::qlueAppQuitOnLastWindowClosed()}
+    }
+
+    /// Sets whether the event loop terminates automatically once the last
+    /// visible window is closed.
+    ///
+    /// This is on by default and is mostly useful to disable for
+    /// applications, such as tray applications, that should keep running
+    /// with no window visible.
+    pub fn set_quit_on_last_window_closed(&self, quit: bool) {
+        unsafe {qlue::qlueAppSetQuitOnLastWindowClosed(quit);}
+    }
 }
 
 impl Clone for AppRef {
@@ -14,7 +14,14 @@ extern crate qlue_sys as qlue;
 extern crate url;
 
 mod app;
+mod list_model;
+mod object;
 mod quick_view;
+mod variant;
 
 pub use app::{App, AppRef};
+pub use list_model::{ListModel, VecListModel};
+pub use object::{Meta, Property, QObject, Signal, Slice, Slot, Type,
+    activate, register_type};
 pub use quick_view::QuickView;
+pub use variant::Variant;
@@ -0,0 +1,187 @@
+// Copyright (C) 2017 Stephane Raux. Distributed under the MIT license.
+
+//! `QAbstractListModel` bridge for feeding dynamic collections to QML
+
+use clue;
+use clue::convert::to_string_view;
+use qlue;
+use std::os::raw::{c_int, c_void};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use variant::Variant;
+
+/// Trait implemented by Rust types backing a QML `ListView`/`Repeater`.
+///
+/// [`VecListModel`] is the implementation that can be exposed to QML: its
+/// `QAbstractListModel` shim calls back into `row_count` and `data`, and
+/// [`VecListModel::as_variant`] hands the shim to
+/// [`QuickView::set_context_property`](::QuickView::set_context_property).
+/// No shim is provided for other implementors of this trait. Use
+/// `VecListModel`'s mutation helpers (`begin_insert_rows`, `data_changed`,
+/// ...) so views animate changes instead of resetting.
+pub trait ListModel {
+    /// Returns the number of rows currently in the model.
+    fn row_count(&self) -> usize;
+
+    /// Returns the value of `role` at `row`.
+    fn data(&self, row: usize, role: i32) -> Variant;
+
+    /// Returns the role ids and names exposed to QML delegates.
+    fn role_names(&self) -> &[(i32, String)];
+}
+
+/// `ListModel` adapter backed by a `Vec<T>`.
+///
+/// Each element of `T` is projected to roles through a closure supplied at
+/// construction, since a plain `Vec<T>` has no notion of QML roles on its
+/// own. The model pins itself behind a `Box` so the `QAbstractListModel`
+/// shim on the C++ side can hold a stable pointer back into it.
+pub struct VecListModel<T> {
+    items: Vec<T>,
+    roles: Vec<(i32, String)>,
+    project: Box<dyn Fn(&T, i32) -> Variant>,
+    im: qlue::QlueListModel,
+}
+
+impl<T> VecListModel<T> {
+    /// Creates a model backed by `items`, with `roles` describing the QML
+    /// role ids/names and `project` mapping an item and role to a
+    /// [`Variant`].
+    pub fn new(items: Vec<T>, roles: Vec<(i32, String)>,
+            project: impl Fn(&T, i32) -> Variant + 'static)
+            -> Box<VecListModel<T>> {
+        let model = Box::new(VecListModel {
+            items,
+            roles,
+            project: Box::new(project),
+            im: unsafe {qlue::qlueListModelNew()},
+        });
+        unsafe {
+            qlue::qlueListModelSetCallbacks(model.im,
+                model.as_ref() as *const VecListModel<T> as *const c_void,
+                Some(row_count::<VecListModel<T>>),
+                Some(data::<VecListModel<T>>));
+            for &(id, ref name) in &model.roles {
+                qlue::qlueListModelAddRoleName(model.im, id as c_int,
+                    to_string_view(name));
+            }
+        }
+        model
+    }
+
+    /// Returns the items currently in the model.
+    pub fn items(&self) -> &[T] {
+        &self.items
+    }
+
+    /// Returns a [`Variant`] referencing this model's QML-facing object,
+    /// for use with
+    /// [`QuickView::set_context_property`](::QuickView::set_context_property).
+    pub fn as_variant(&self) -> Variant {
+        unsafe {Variant::Object(qlue::qlueListModelAsObject(self.im))}
+    }
+
+    /// Appends `item`, notifying QML views so they animate the insertion.
+    pub fn push(&mut self, item: T) {
+        let row = self.items.len();
+        self.begin_insert_rows(row, row);
+        self.items.push(item);
+        self.end_insert_rows();
+    }
+
+    /// Removes the item at `row`, notifying QML views so they animate the
+    /// removal.
+    ///
+    /// # Panics
+    /// Panics if `row` is out of bounds.
+    pub fn remove(&mut self, row: usize) -> T {
+        self.begin_remove_rows(row, row);
+        let item = self.items.remove(row);
+        self.end_remove_rows();
+        item
+    }
+
+    /// Replaces the item at `row` and notifies QML views that its data
+    /// changed.
+    ///
+    /// # Panics
+    /// Panics if `row` is out of bounds.
+    pub fn set(&mut self, row: usize, item: T) {
+        self.items[row] = item;
+        self.data_changed(row);
+    }
+
+    /// Signals that rows `[first, last]` are about to be inserted.
+    ///
+    /// Must be followed by [`end_insert_rows`](VecListModel::end_insert_rows)
+    /// once the rows have actually been added.
+    pub fn begin_insert_rows(&self, first: usize, last: usize) {
+        unsafe {
+            qlue::qlueListModelBeginInsertRows(self.im, first as c_int,
+                last as c_int);
+        }
+    }
+
+    /// Signals that a previously announced row insertion is complete.
+    pub fn end_insert_rows(&self) {
+        unsafe {qlue::qlueListModelEndInsertRows(self.im);}
+    }
+
+    /// Signals that rows `[first, last]` are about to be removed.
+    ///
+    /// Must be followed by [`end_remove_rows`](VecListModel::end_remove_rows)
+    /// once the rows have actually been removed.
+    pub fn begin_remove_rows(&self, first: usize, last: usize) {
+        unsafe {
+            qlue::qlueListModelBeginRemoveRows(self.im, first as c_int,
+                last as c_int);
+        }
+    }
+
+    /// Signals that a previously announced row removal is complete.
+    pub fn end_remove_rows(&self) {
+        unsafe {qlue::qlueListModelEndRemoveRows(self.im);}
+    }
+
+    /// Signals that the data at `row` changed, without altering row count.
+    pub fn data_changed(&self, row: usize) {
+        unsafe {
+            qlue::qlueListModelDataChanged(self.im, row as c_int,
+                row as c_int);
+        }
+    }
+}
+
+impl<T> ListModel for VecListModel<T> {
+    fn row_count(&self) -> usize {
+        self.items.len()
+    }
+
+    fn data(&self, row: usize, role: i32) -> Variant {
+        (self.project)(&self.items[row], role)
+    }
+
+    fn role_names(&self) -> &[(i32, String)] {
+        &self.roles
+    }
+}
+
+impl<T> Drop for VecListModel<T> {
+    fn drop(&mut self) {
+        unsafe {qlue::qlueListModelDelete(self.im);}
+    }
+}
+
+unsafe extern fn row_count<M: ListModel>(model: *const c_void) -> c_int {
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        (*(model as *const M)).row_count() as c_int
+    }));
+    result.unwrap_or(0)
+}
+
+unsafe extern fn data<M: ListModel>(model: *const c_void, row: c_int,
+        role: c_int, out: clue::ClueVariant) {
+    let _ = catch_unwind(AssertUnwindSafe(|| {
+        let value = (*(model as *const M)).data(row as usize, role);
+        clue::convert::write_variant(out, &value.to_ffi());
+    }));
+}
@@ -0,0 +1,212 @@
+// Copyright (C) 2017 Stephane Raux. Distributed under the MIT license.
+
+//! QObject subsystem exposing Rust values to QML
+
+use clue;
+use qlue;
+use std::os::raw::{c_int, c_void};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::ptr;
+use std::slice;
+
+/// FFI-safe view over a `'static` slice.
+///
+/// `&'static [T]` has no defined C-compatible layout, so [`Meta`] and its
+/// parts cross the `qlue` boundary through this explicit `(ptr, len)` pair
+/// instead.
+#[repr(C)]
+#[derive(Debug)]
+pub struct Slice<T: 'static> {
+    ptr: *const T,
+    len: usize,
+}
+
+impl<T> Slice<T> {
+    /// Views this `Slice` as a Rust slice.
+    pub fn as_slice(&self) -> &'static [T] {
+        unsafe {slice::from_raw_parts(self.ptr, self.len)}
+    }
+}
+
+impl<T> Clone for Slice<T> {
+    fn clone(&self) -> Slice<T> {
+        *self
+    }
+}
+
+impl<T> Copy for Slice<T> {}
+
+impl<T> From<&'static [T]> for Slice<T> {
+    fn from(s: &'static [T]) -> Slice<T> {
+        Slice {ptr: s.as_ptr(), len: s.len()}
+    }
+}
+
+/// Qt property type tag understood by the synthetic meta-object.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Type {
+    /// `int`
+    Int = 0,
+    /// `double`
+    Double = 1,
+    /// `bool`
+    Bool = 2,
+    /// `QString`
+    String = 3,
+    /// `QObject*`
+    QObject = 4,
+}
+
+/// Describes a property exposed to QML.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct Property {
+    /// Property name, as used from QML.
+    pub name: &'static str,
+    /// Qt type of the property.
+    pub ty: Type,
+    /// Index passed back to [`QObject::get_property`].
+    pub getter: usize,
+    /// Index passed back to [`QObject::set_property`], if the property is
+    /// writable.
+    pub setter: Option<usize>,
+    /// Index of the signal in [`Meta::signals`] emitted when the property
+    /// changes, if any.
+    pub notify_signal: Option<usize>,
+}
+
+/// Describes a signal exposed to QML.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct Signal {
+    /// Signal name, as used from QML.
+    pub name: &'static str,
+    /// Types of the signal arguments.
+    pub args: Slice<Type>,
+}
+
+/// Describes an invokable slot exposed to QML.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct Slot {
+    /// Slot name, as used from QML.
+    pub name: &'static str,
+    /// Index passed back to [`QObject::invoke`].
+    pub index: usize,
+    /// Types of the slot arguments.
+    pub args: Slice<Type>,
+    /// Type of the return value, if any.
+    pub ret: Option<Type>,
+}
+
+/// Metadata describing the shape of a [`QObject`] implementation.
+///
+/// `register_type` uses this to build the synthetic `QMetaObject` that lets
+/// Qt see a Rust type's properties, signals, and slots as if they were
+/// declared in C++.
+#[repr(C)]
+#[derive(Debug)]
+pub struct Meta {
+    /// Name under which the type is registered with QML.
+    pub class_name: &'static str,
+    /// Properties exposed to QML.
+    pub properties: Slice<Property>,
+    /// Signals exposed to QML.
+    pub signals: Slice<Signal>,
+    /// Slots exposed to QML.
+    pub slots: Slice<Slot>,
+}
+
+/// Trait implemented by Rust types instantiable from QML as `QObject`s.
+///
+/// Implementors describe their shape through [`Meta`] and answer dispatch
+/// from `qt_metacall` for property access and slot invocation. Signals are
+/// emitted with the free function [`activate`].
+pub trait QObject: Default + Send + 'static {
+    /// Returns the metadata describing this type's properties, signals and
+    /// slots.
+    fn meta() -> &'static Meta;
+
+    /// Reads the property at `index` and writes it into `out`.
+    fn get_property(&self, index: usize, out: clue::ClueVariant);
+
+    /// Writes `value` into the property at `index`.
+    fn set_property(&mut self, index: usize, value: clue::ClueVariant);
+
+    /// Invokes the slot at `index` with `args`, writing the return value,
+    /// if any, into `ret`.
+    fn invoke(&mut self, index: usize, args: &[clue::ClueVariant],
+        ret: clue::ClueVariant);
+}
+
+/// Registers `T` as an instantiable QML type.
+///
+/// Mirrors `qmlRegisterType<T>(uri, major, minor, name)`: once registered, a
+/// QML document can `import uri major.minor` and instantiate `name {}`, with
+/// property reads/writes, slot calls, and signal activation routed into
+/// `T`'s [`QObject`] implementation.
+pub fn register_type<T: QObject>(uri: &str, major: i32, minor: i32,
+        name: &str) {
+    use clue::convert::to_string_view;
+    unsafe {
+        qlue::qlueRegisterType(to_string_view(uri), major as c_int,
+            minor as c_int, to_string_view(name), meta_object::<T>(),
+            Some(instantiate::<T>), Some(destroy::<T>),
+            Some(get_property::<T>), Some(set_property::<T>),
+            Some(invoke::<T>));
+    }
+}
+
+/// Emits the signal at `signal_index` on `obj`, notifying QML connections.
+///
+/// `signal_index` refers to [`Meta::signals`] as returned by `T::meta()`.
+pub fn activate<T: QObject>(obj: &T, signal_index: usize,
+        args: &[clue::ClueVariant]) {
+    unsafe {
+        qlue::qlueObjectActivate(obj as *const T as *const c_void,
+            signal_index as c_int, args.as_ptr(), args.len() as c_int);
+    }
+}
+
+fn meta_object<T: QObject>() -> qlue::QlueMetaObject {
+    unsafe {qlue::qlueMetaObjectFromMeta(T::meta() as *const Meta as *const c_void)}
+}
+
+unsafe extern fn instantiate<T: QObject>() -> *mut c_void {
+    let result = catch_unwind(|| Box::into_raw(Box::new(T::default())));
+    result.unwrap_or(ptr::null_mut()) as *mut c_void
+}
+
+unsafe extern fn destroy<T: QObject>(obj: *mut c_void) {
+    let _ = catch_unwind(AssertUnwindSafe(|| {
+        drop(Box::from_raw(obj as *mut T));
+    }));
+}
+
+unsafe extern fn get_property<T: QObject>(obj: *const c_void, index: c_int,
+        out: clue::ClueVariant) {
+    let _ = catch_unwind(AssertUnwindSafe(|| {
+        (*(obj as *const T)).get_property(index as usize, out);
+    }));
+}
+
+unsafe extern fn set_property<T: QObject>(obj: *mut c_void, index: c_int,
+        value: clue::ClueVariant) {
+    let _ = catch_unwind(AssertUnwindSafe(|| {
+        (*(obj as *mut T)).set_property(index as usize, value);
+    }));
+}
+
+unsafe extern fn invoke<T: QObject>(obj: *mut c_void, index: c_int,
+        args: *const clue::ClueVariant, arg_count: c_int,
+        ret: clue::ClueVariant) {
+    let _ = catch_unwind(AssertUnwindSafe(|| {
+        let args = if args.is_null() {
+            &[]
+        } else {
+            slice::from_raw_parts(args, arg_count as usize)
+        };
+        (*(obj as *mut T)).invoke(index as usize, args, ret);
+    }));
+}
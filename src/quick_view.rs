@@ -3,18 +3,63 @@
 //! QML view module
 
 use App;
-use clue::convert::to_string_view;
+use clue;
+use clue::convert::{from_string_view_lossy, to_string_view};
 use qlue;
+use std::cell::RefCell;
+use std::fmt;
 use std::marker::PhantomData;
+use std::os::raw::{c_int, c_void};
+use std::panic::{catch_unwind, AssertUnwindSafe};
 use url::Url;
+use variant::Variant;
+
+/// Callback invoked on `statusChanged`.
+///
+/// Boxed separately from `QuickView` so the pointer handed to the `qlue`
+/// shim stays valid even if the `QuickView` itself is later moved; only
+/// the heap allocation backing this box needs to stay put.
+type StatusCallback = Box<RefCell<Option<Box<dyn FnMut(Status)>>>>;
+
+/// Load status of a `QuickView`'s root QML component.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    /// No source has been set yet.
+    Null,
+    /// The component loaded successfully.
+    Ready,
+    /// The component is being loaded, typically because its URL is remote.
+    Loading,
+    /// The component failed to load. See `QuickView::errors`.
+    Error,
+}
+
+/// Error reported while loading a QML component.
+#[derive(Debug, Clone)]
+pub struct QmlError {
+    /// Human-readable description of the error.
+    pub message: String,
+    /// URL of the document the error was found in.
+    pub url: String,
+    /// Line the error was found at, if known.
+    pub line: i32,
+    /// Column the error was found at, if known.
+    pub column: i32,
+}
 
 /// View to load a QML scene.
 ///
 /// Wraps `QQuickView`.
-#[derive(Debug)]
 pub struct QuickView<'a> {
     app: PhantomData<&'a App>,
     im: qlue::QlueQuickView,
+    status_callback: StatusCallback,
+}
+
+impl<'a> fmt::Debug for QuickView<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("QuickView").field("im", &self.im).finish()
+    }
 }
 
 impl<'a> QuickView<'a> {
@@ -24,21 +69,111 @@ impl<'a> QuickView<'a> {
             QuickView {
                 app: PhantomData,
                 im: qlue::qlueQuickViewNew(),
+                status_callback: Box::new(RefCell::new(None)),
             }
         }
     }
 
     /// Loads QML file into the view.
-    pub fn set_source(&self, url: &Url) {
+    ///
+    /// `url` is not limited to `file:` URLs: a `qrc:/path/to/file.qml` URL
+    /// loads from Qt resources compiled into the executable, which avoids
+    /// shipping loose `.qml` files alongside the binary. `Url::parse`
+    /// accepts `qrc` as an opaque scheme, so the raw string reaches Qt
+    /// unmodified.
+    ///
+    /// Returns the errors reported by the QML engine, if any. A successful
+    /// return does not guarantee the component is `Ready` yet; use
+    /// [`status`](QuickView::status) or
+    /// [`on_status_changed`](QuickView::on_status_changed) for sources, such
+    /// as remote URLs, that load asynchronously.
+    pub fn set_source(&self, url: &Url) -> Result<(), Vec<QmlError>> {
         unsafe {
             qlue::qlueQuickViewSetSource(self.im, to_string_view(url.as_str()));
         }
+        match self.status() {
+            Status::Error => Err(self.errors()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Loads the view's root QML component from the in-memory string `qml`
+    /// instead of a file.
+    ///
+    /// `base_url` resolves any relative imports or child components
+    /// referenced from `qml`. This lets an application embed QML with
+    /// `include_str!` and ship a single binary, rather than loading loose
+    /// `.qml` files through a `CARGO_MANIFEST_DIR`-relative path.
+    pub fn set_source_data(&self, qml: &str, base_url: &Url)
+            -> Result<(), Vec<QmlError>> {
+        unsafe {
+            qlue::qlueQuickViewSetSourceData(self.im, to_string_view(qml),
+                to_string_view(base_url.as_str()));
+        }
+        match self.status() {
+            Status::Error => Err(self.errors()),
+            _ => Ok(()),
+        }
     }
 
     /// Makes the view visible.
     pub fn show(&self) {
         unsafe {qlue::qlueQuickViewShow(self.im);}
     }
+
+    /// Exposes `value` to the view's root QML context under `name`.
+    ///
+    /// This is how a Rust-side controller or initial data reaches a QML
+    /// document, typically a [`QObject`](::QObject) registered with
+    /// [`register_type`](::register_type). Call this before [`set_source`]
+    /// so the root document can refer to `name` as soon as it loads.
+    ///
+    /// [`set_source`]: QuickView::set_source
+    pub fn set_context_property(&self, name: &str, value: impl Into<Variant>) {
+        unsafe {
+            qlue::qlueQuickViewRootContextSetContextProperty(self.im,
+                to_string_view(name), value.into().to_ffi());
+        }
+    }
+
+    /// Returns the load status of the root QML component.
+    pub fn status(&self) -> Status {
+        unsafe {
+            match qlue::qlueQuickViewStatus(self.im) {
+                qlue::QlueStatus::QlueStatusNull => Status::Null,
+                qlue::QlueStatus::QlueStatusReady => Status::Ready,
+                qlue::QlueStatus::QlueStatusLoading => Status::Loading,
+                qlue::QlueStatus::QlueStatusError => Status::Error,
+            }
+        }
+    }
+
+    /// Returns the errors reported while loading the root QML component.
+    ///
+    /// Empty unless [`status`](QuickView::status) is `Error`.
+    pub fn errors(&self) -> Vec<QmlError> {
+        let mut errors = Vec::new();
+        unsafe {
+            qlue::qlueQuickViewErrors(self.im,
+                &mut errors as *mut Vec<QmlError> as *mut c_void,
+                Some(push_error));
+        }
+        errors
+    }
+
+    /// Registers `callback` to be called whenever
+    /// [`status`](QuickView::status) changes.
+    ///
+    /// Useful to observe scenes loaded from remote URLs, which go through
+    /// `Loading` before settling on `Ready` or `Error`.
+    pub fn on_status_changed(&self, callback: impl FnMut(Status) + 'static) {
+        *self.status_callback.borrow_mut() = Some(Box::new(callback));
+        unsafe {
+            qlue::qlueQuickViewOnStatusChanged(self.im,
+                &*self.status_callback as *const RefCell<_> as *mut c_void,
+                Some(status_changed));
+        }
+    }
 }
 
 impl<'a> Drop for QuickView<'a> {
@@ -46,3 +181,34 @@ impl<'a> Drop for QuickView<'a> {
         unsafe {qlue::qlueQuickViewDelete(self.im);}
     }
 }
+
+unsafe extern fn push_error(errors: *mut c_void, message: clue::ClueStringView,
+        url: clue::ClueStringView, line: c_int, column: c_int) {
+    let _ = catch_unwind(AssertUnwindSafe(|| {
+        let errors = &mut *(errors as *mut Vec<QmlError>);
+        errors.push(QmlError {
+            message: from_string_view_lossy(message),
+            url: from_string_view_lossy(url),
+            line: line as i32,
+            column: column as i32,
+        });
+    }));
+}
+
+unsafe extern fn status_changed(status_callback: *mut c_void,
+        status: qlue::QlueStatus) {
+    let _ = catch_unwind(AssertUnwindSafe(|| {
+        let status_callback =
+            &*(status_callback
+                as *const RefCell<Option<Box<dyn FnMut(Status)>>>);
+        let status = match status {
+            qlue::QlueStatus::QlueStatusNull => Status::Null,
+            qlue::QlueStatus::QlueStatusReady => Status::Ready,
+            qlue::QlueStatus::QlueStatusLoading => Status::Loading,
+            qlue::QlueStatus::QlueStatusError => Status::Error,
+        };
+        if let Some(ref mut callback) = *status_callback.borrow_mut() {
+            callback(status);
+        }
+    }));
+}
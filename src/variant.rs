@@ -0,0 +1,76 @@
+// Copyright (C) 2017 Stephane Raux. Distributed under the MIT license.
+
+//! `QVariant` bridge
+
+use clue;
+use clue::convert::to_string_view;
+use object::QObject;
+use qlue;
+use std::os::raw::c_void;
+
+/// Value that can cross the Rust/QML boundary as a `QVariant`.
+#[derive(Debug)]
+pub enum Variant {
+    /// `int`
+    Int(i64),
+    /// `double`
+    Double(f64),
+    /// `bool`
+    Bool(bool),
+    /// `QString`
+    String(String),
+    /// `QObject*`, as registered with [`register_type`](::register_type).
+    Object(*mut c_void),
+}
+
+impl Variant {
+    /// Converts this value to the FFI `QVariant` representation.
+    pub(crate) fn to_ffi(&self) -> clue::ClueVariant {
+        unsafe {
+            match *self {
+                Variant::Int(n) => qlue::qlueVariantFromInt(n),
+                Variant::Double(n) => qlue::qlueVariantFromDouble(n),
+                Variant::Bool(b) => qlue::qlueVariantFromBool(b),
+                Variant::String(ref s) =>
+                    qlue::qlueVariantFromString(to_string_view(s)),
+                Variant::Object(p) => qlue::qlueVariantFromObject(p),
+            }
+        }
+    }
+}
+
+impl From<i64> for Variant {
+    fn from(n: i64) -> Variant {
+        Variant::Int(n)
+    }
+}
+
+impl From<f64> for Variant {
+    fn from(n: f64) -> Variant {
+        Variant::Double(n)
+    }
+}
+
+impl From<bool> for Variant {
+    fn from(b: bool) -> Variant {
+        Variant::Bool(b)
+    }
+}
+
+impl From<String> for Variant {
+    fn from(s: String) -> Variant {
+        Variant::String(s)
+    }
+}
+
+impl<'a> From<&'a str> for Variant {
+    fn from(s: &'a str) -> Variant {
+        Variant::String(s.to_owned())
+    }
+}
+
+impl<T: QObject> From<*mut T> for Variant {
+    fn from(obj: *mut T) -> Variant {
+        Variant::Object(obj as *mut c_void)
+    }
+}